@@ -1,18 +1,23 @@
 use clap::Parser;
-use log::{error, info};
+use log::{error, info, warn};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, watch};
 
 mod errors;
 mod instrumentors;
 mod opentelemetry_controller;
 mod process;
+mod self_telemetry;
+mod supervisor;
 
 use errors::Result;
-use instrumentors::Manager;
+use instrumentors::{Manager, RunOutcome};
 use opentelemetry_controller::Controller;
-use process::{Analyzer, TargetArgs};
+use process::{Analyzer, TargetArgs, TargetDetails};
+use self_telemetry::{AgentMetrics, RuntimeMetadata};
+use supervisor::OnTargetExit;
 
 #[derive(Parser, Debug)]
 #[command(name = "otel-rust-agent")]
@@ -26,19 +31,89 @@ struct Args {
     #[arg(long, env = "OTEL_TARGET_PID")]
     target_pid: Option<i32>,
 
+    /// Launch the target under the agent instead of attaching to an existing
+    /// process. Takes the command and its arguments, e.g.
+    /// `--target-cmd /usr/bin/my-server --port 8080`. Every argument after
+    /// it, including ones starting with `-`, goes to the target, so it must
+    /// come last on the command line.
+    #[arg(long, env = "OTEL_TARGET_CMD", num_args = 1.., allow_hyphen_values = true, value_name = "CMD")]
+    target_cmd: Option<Vec<String>>,
+
     #[arg(long, env = "OTEL_SERVICE_NAME")]
     service_name: String,
 
     #[arg(long, env = "OTEL_EXPORTER_OTLP_ENDPOINT", default_value = "http://localhost:4317")]
     otlp_endpoint: String,
 
+    /// Export over vsock instead of TCP, as "<cid>:<port>" of the host-side collector.
+    #[arg(long, env = "OTEL_EXPORTER_OTLP_VSOCK", value_name = "CID:PORT")]
+    otlp_vsock: Option<String>,
+
     #[arg(long, env = "OTEL_STDOUT", default_value = "false")]
     stdout: bool,
 
+    /// What to do when the target process exits while the agent is running.
+    #[arg(long, env = "OTEL_ON_TARGET_EXIT", value_enum, default_value = "shutdown")]
+    on_target_exit: OnTargetExit,
+
+    /// Signal sent to a `--target-cmd` launched target when the agent shuts
+    /// down, e.g. "SIGTERM" or "TERM". A target attached to via
+    /// `--target-pid` or `--target-exe` is not signaled.
+    #[arg(long, env = "OTEL_STOP_SIGNAL", default_value = "SIGTERM")]
+    stop_signal: String,
+
+    /// How long to wait after `--stop-signal` before escalating to SIGKILL.
+    #[arg(long, env = "OTEL_STOP_TIMEOUT_SECS", default_value = "10")]
+    stop_timeout_secs: u64,
+
     #[arg(long, short, default_value = "info")]
     log_level: String,
 }
 
+/// Parses a `--otlp-vsock` value of the form `"<cid>:<port>"`.
+fn parse_vsock_addr(value: &str) -> Result<(u32, u32)> {
+    let (cid, port) = value.split_once(':').ok_or_else(|| {
+        errors::Error::InvalidTarget(format!(
+            "invalid --otlp-vsock value {:?}, expected CID:PORT",
+            value
+        ))
+    })?;
+
+    let cid = cid
+        .parse::<u32>()
+        .map_err(|e| errors::Error::InvalidTarget(format!("invalid vsock CID {:?}: {}", cid, e)))?;
+    let port = port.parse::<u32>().map_err(|e| {
+        errors::Error::InvalidTarget(format!("invalid vsock port {:?}: {}", port, e))
+    })?;
+
+    Ok((cid, port))
+}
+
+#[cfg(test)]
+mod vsock_addr_tests {
+    use super::parse_vsock_addr;
+
+    #[test]
+    fn parse_vsock_addr_accepts_cid_and_port() {
+        assert_eq!(parse_vsock_addr("3:4317").unwrap(), (3, 4317));
+    }
+
+    #[test]
+    fn parse_vsock_addr_rejects_missing_colon() {
+        assert!(parse_vsock_addr("34317").is_err());
+    }
+
+    #[test]
+    fn parse_vsock_addr_rejects_non_numeric_cid() {
+        assert!(parse_vsock_addr("abc:4317").is_err());
+    }
+
+    #[test]
+    fn parse_vsock_addr_rejects_non_numeric_port() {
+        assert!(parse_vsock_addr("3:abc").is_err());
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
@@ -52,6 +127,7 @@ async fn main() -> Result<()> {
     let target = TargetArgs {
         exe_path: args.target_exe,
         pid: args.target_pid,
+        command: args.target_cmd,
     };
 
     if let Err(e) = target.validate() {
@@ -59,18 +135,35 @@ async fn main() -> Result<()> {
         return Err(e);
     }
 
-    let (shutdown_tx, _) = broadcast::channel::<()>(1);
-    let shutdown_rx = shutdown_tx.subscribe();
+    if args.on_target_exit == OnTargetExit::Restart && target.pid.is_some() {
+        // `discover_process` in PID mode just re-checks the same fixed PID;
+        // once the target exits that PID is gone for good, so "restart"
+        // would immediately hit ProcessNotFound instead of ever restarting.
+        let e = errors::Error::InvalidTarget(
+            "--on-target-exit=restart is not supported with --target-pid, since the same PID \
+             can't come back; use --target-exe or --target-cmd instead"
+                .to_string(),
+        );
+        error!("Invalid target args: {}", e);
+        return Err(e);
+    }
 
-    let controller = if args.stdout {
-        Controller::new_stdout(&args.service_name)?
-    } else {
-        Controller::new(&args.otlp_endpoint, &args.service_name)?
+    let stop_signal = match supervisor::parse_signal(&args.stop_signal) {
+        Ok(signal) => signal,
+        Err(e) => {
+            error!("Invalid --stop-signal: {}", e);
+            return Err(e);
+        }
     };
 
-    let controller = Arc::new(controller);
+    let launched = target.command.is_some();
+
+    let (shutdown_tx, _) = broadcast::channel::<()>(1);
+    let shutdown_rx = shutdown_tx.subscribe();
+
     let analyzer = Analyzer::new();
-    let manager = Manager::new(Arc::clone(&controller));
+    let metrics = Arc::new(AgentMetrics::new());
+    let manager = Manager::new(Arc::clone(&metrics));
 
     let shutdown_tx_clone = shutdown_tx.clone();
     tokio::spawn(async move {
@@ -79,7 +172,7 @@ async fn main() -> Result<()> {
         let _ = shutdown_tx_clone.send(());
     });
 
-    let pid = match analyzer.discover_process(&target).await {
+    let mut pid = match analyzer.discover_process(&target).await {
         Ok(pid) => pid,
         Err(e) => {
             error!("Failed to discover process: {}", e);
@@ -89,10 +182,13 @@ async fn main() -> Result<()> {
 
     info!("Found target process with PID: {}", pid);
 
-    let target_details = match analyzer.analyze(pid, manager.get_relevant_funcs()).await {
+    let mut target_details = match analyzer.analyze(pid, manager.get_relevant_funcs().await).await {
         Ok(details) => details,
         Err(e) => {
             error!("Failed to analyze target process: {}", e);
+            if launched {
+                process::Analyzer::kill_launched(pid);
+            }
             return Err(e);
         }
     };
@@ -103,16 +199,182 @@ async fn main() -> Result<()> {
         target_details.functions.len()
     );
 
-    manager.filter_unused_instrumentors(&target_details);
+    let runtime_metadata = RuntimeMetadata::detect();
 
-    info!("Invoking instrumentors...");
-    if let Err(e) = manager.run(&target_details, shutdown_rx).await {
-        if !matches!(e, errors::Error::Interrupted) {
-            error!("Error running instrumentors: {}", e);
+    let build_controller = |target: &TargetDetails| -> Result<Controller> {
+        if args.stdout {
+            Controller::new_stdout(&args.service_name, &runtime_metadata, target)
+        } else if let Some(ref vsock_addr) = args.otlp_vsock {
+            let (cid, port) = parse_vsock_addr(vsock_addr)?;
+            Controller::new_vsock(cid, port, &args.service_name, &runtime_metadata, target)
+        } else {
+            Controller::new(&args.otlp_endpoint, &args.service_name, &runtime_metadata, target)
+        }
+    };
+
+    let mut controller = Arc::new(build_controller(&target_details)?);
+
+    let (controller_tx, mut controller_rx) = watch::channel(Arc::clone(&controller));
+    let self_telemetry_metrics = Arc::clone(&metrics);
+    let mut self_telemetry_shutdown_rx = shutdown_tx.subscribe();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let self_telemetry_controller = controller_rx.borrow_and_update().clone();
+                    self_telemetry_metrics.report(self_telemetry_controller.tracer());
+                }
+                _ = self_telemetry_shutdown_rx.recv() => break,
+            }
+        }
+    });
+
+    manager.filter_unused_instrumentors(&target_details).await;
+    let mut loaded = manager.load_all(&target_details).await;
+
+    // Resume a launched target only once instrumentors have actually loaded
+    // against it, so it never runs a single instruction unobserved.
+    if launched {
+        if let Err(e) = process::Analyzer::resume_launched(pid) {
+            error!("Failed to resume launched target: {}", e);
+            process::Analyzer::kill_launched(pid);
             return Err(e);
         }
     }
 
+    info!("Invoking instrumentors...");
+    let mut detached = false;
+    'supervise: loop {
+        match manager
+            .run(&loaded, &target_details, shutdown_rx.resubscribe(), Arc::clone(&controller))
+            .await
+        {
+            Err(errors::Error::Interrupted) => break 'supervise,
+            Err(e) => {
+                error!("Error running instrumentors: {}", e);
+                return Err(e);
+            }
+            Ok(RunOutcome::EventsHandlerDone) => {
+                info!("Events handler completed");
+                break 'supervise;
+            }
+            Ok(RunOutcome::TargetExited) => match args.on_target_exit {
+                OnTargetExit::Shutdown => {
+                    info!("Target process exited, shutting down (--on-target-exit=shutdown)");
+                    break 'supervise;
+                }
+                OnTargetExit::Detach => {
+                    info!(
+                        "Target process exited, detaching (--on-target-exit=detach): \
+                         keeping the export pipeline up to flush buffered spans"
+                    );
+                    detached = true;
+                    break 'supervise;
+                }
+                OnTargetExit::Restart => {
+                    info!("Target process exited, restarting (--on-target-exit=restart)");
+
+                    if launched {
+                        if let Err(e) = process::Analyzer::reap_launched(pid) {
+                            warn!("Failed to reap previous launched target PID {}: {}", pid, e);
+                        }
+                    }
+
+                    pid = match analyzer.discover_process(&target).await {
+                        Ok(pid) => pid,
+                        Err(e) => {
+                            error!("Failed to re-discover process for restart: {}", e);
+                            return Err(e);
+                        }
+                    };
+
+                    target_details =
+                        match analyzer.analyze(pid, manager.get_relevant_funcs().await).await
+                        {
+                            Ok(details) => details,
+                            Err(e) => {
+                                error!("Failed to re-analyze restarted process: {}", e);
+                                if launched {
+                                    process::Analyzer::kill_launched(pid);
+                                }
+                                return Err(e);
+                            }
+                        };
+
+                    manager.filter_unused_instrumentors(&target_details).await;
+                    loaded = manager.load_all(&target_details).await;
+
+                    // The restarted process has a new pid/exe_path, so the
+                    // exported Resource (and the tracer built from it) must
+                    // be rebuilt too, or every subsequent span keeps
+                    // attributing telemetry to the dead, pre-restart process.
+                    controller = match build_controller(&target_details) {
+                        Ok(c) => Arc::new(c),
+                        Err(e) => {
+                            error!("Failed to rebuild OTLP controller for restarted target: {}", e);
+                            if launched {
+                                process::Analyzer::kill_launched(pid);
+                            }
+                            return Err(e);
+                        }
+                    };
+                    let _ = controller_tx.send(Arc::clone(&controller));
+
+                    if launched {
+                        if let Err(e) = process::Analyzer::resume_launched(pid) {
+                            error!("Failed to resume restarted target: {}", e);
+                            process::Analyzer::kill_launched(pid);
+                            return Err(e);
+                        }
+                    }
+
+                    info!("Re-loaded instrumentors against restarted PID: {}", pid);
+                }
+            },
+        }
+    }
+
+    if detached {
+        // The target already exited on its own; a launched child is left a
+        // zombie until reaped, so clear it here rather than signaling/killing
+        // anything. The agent itself stays up (self-telemetry and any
+        // in-flight batch export keep running) until an explicit shutdown.
+        if launched {
+            match process::Analyzer::reap_launched(pid) {
+                Ok(code) => info!("Reaped exited target, exit code {}", code),
+                Err(e) => error!("Failed to reap exited target: {}", e),
+            }
+        }
+
+        info!("Detached; idling until shutdown signal");
+        let mut detach_shutdown_rx = shutdown_tx.subscribe();
+        let _ = detach_shutdown_rx.recv().await;
+    } else if launched && supervisor::is_alive(target_details.pid) {
+        // Only a process we launched ourselves is ours to signal; a target
+        // merely attached to via --target-pid/--target-exe is left running
+        // so Ctrl-C on the agent can't kill someone else's process.
+        supervisor::stop_target(
+            target_details.pid,
+            stop_signal,
+            Duration::from_secs(args.stop_timeout_secs),
+        )
+        .await;
+    }
+
+    if launched && !detached {
+        match process::Analyzer::reap_launched(pid) {
+            Ok(code) => {
+                info!("Target process exited, propagating exit code {}", code);
+                info!("Agent shutdown complete");
+                std::process::exit(code);
+            }
+            Err(e) => {
+                error!("Failed to reap launched target: {}", e);
+            }
+        }
+    }
+
     info!("Agent shutdown complete");
     Ok(())
 }
@@ -134,6 +396,9 @@ mod errors {
         #[error("eBPF error: {0}")]
         Ebpf(String),
 
+        #[error("Failed to launch target process: {0}")]
+        ProcessLaunch(String),
+
         #[error("OpenTelemetry error: {0}")]
         OpenTelemetry(String),
 
@@ -149,10 +414,17 @@ mod errors {
 
 mod opentelemetry_controller {
     use super::errors::{Error, Result};
+    use super::process::TargetDetails;
+    use super::self_telemetry::RuntimeMetadata;
     use opentelemetry::trace::TracerProvider;
+    use opentelemetry::KeyValue;
     use opentelemetry_otlp::WithExportConfig;
     use opentelemetry_sdk::trace::Tracer;
+    use opentelemetry_sdk::Resource;
     use std::time::Duration;
+    use tokio_vsock::VsockStream;
+    use tonic::transport::{Endpoint, Uri};
+    use tower::service_fn;
 
     pub struct Controller {
         tracer: Tracer,
@@ -160,25 +432,60 @@ mod opentelemetry_controller {
     }
 
     impl Controller {
-        pub fn new(endpoint: &str, service_name: &str) -> Result<Self> {
+        pub fn new(
+            endpoint: &str,
+            service_name: &str,
+            runtime_metadata: &RuntimeMetadata,
+            target: &TargetDetails,
+        ) -> Result<Self> {
             let exporter = opentelemetry_otlp::new_exporter()
                 .tonic()
                 .with_endpoint(endpoint)
                 .with_timeout(Duration::from_secs(10));
 
-            let provider = opentelemetry_otlp::new_pipeline()
+            let tracer = opentelemetry_otlp::new_pipeline()
                 .tracing()
                 .with_exporter(exporter)
-                .with_trace_config(
-                    opentelemetry_sdk::trace::Config::default()
-                        .with_resource(opentelemetry_sdk::Resource::new(vec![
-                            opentelemetry::KeyValue::new("service.name", service_name.to_string()),
-                        ])),
-                )
+                .with_trace_config(opentelemetry_sdk::trace::Config::default().with_resource(
+                    Self::build_resource(service_name, runtime_metadata, target),
+                ))
                 .install_batch(opentelemetry_sdk::runtime::Tokio)
                 .map_err(|e| Error::OpenTelemetry(e.to_string()))?;
 
-            let tracer = provider.tracer("rust-auto-instrumentation");
+            Ok(Self {
+                tracer,
+                service_name: service_name.to_string(),
+            })
+        }
+
+        /// Exports over vsock instead of TCP, for agents running inside a
+        /// microVM or container-on-VM that reach the collector across the VM
+        /// boundary. Only the transport differs; the batch pipeline and
+        /// resource configuration are identical to `new`.
+        pub fn new_vsock(
+            cid: u32,
+            port: u32,
+            service_name: &str,
+            runtime_metadata: &RuntimeMetadata,
+            target: &TargetDetails,
+        ) -> Result<Self> {
+            let channel = Endpoint::from_static("http://[::]:0").connect_with_connector_lazy(
+                service_fn(move |_: Uri| VsockStream::connect(cid, port)),
+            );
+
+            let exporter = opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_channel(channel)
+                .with_timeout(Duration::from_secs(10));
+
+            let tracer = opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(exporter)
+                .with_trace_config(opentelemetry_sdk::trace::Config::default().with_resource(
+                    Self::build_resource(service_name, runtime_metadata, target),
+                ))
+                .install_batch(opentelemetry_sdk::runtime::Tokio)
+                .map_err(|e| Error::OpenTelemetry(e.to_string()))?;
 
             Ok(Self {
                 tracer,
@@ -186,12 +493,14 @@ mod opentelemetry_controller {
             })
         }
 
-        pub fn new_stdout(service_name: &str) -> Result<Self> {
+        pub fn new_stdout(
+            service_name: &str,
+            runtime_metadata: &RuntimeMetadata,
+            target: &TargetDetails,
+        ) -> Result<Self> {
             let provider = opentelemetry_sdk::trace::TracerProvider::builder()
                 .with_simple_exporter(opentelemetry_stdout::SpanExporter::default())
-                .with_resource(opentelemetry_sdk::Resource::new(vec![
-                    opentelemetry::KeyValue::new("service.name", service_name.to_string()),
-                ]))
+                .with_resource(Self::build_resource(service_name, runtime_metadata, target))
                 .build();
 
             let tracer = provider.tracer("rust-auto-instrumentation");
@@ -202,6 +511,47 @@ mod opentelemetry_controller {
             })
         }
 
+        /// Builds the OTLP `Resource` shared by every exporter variant:
+        /// the configured service name plus whatever the agent can detect
+        /// about the process it's attached to and the host/runtime it's
+        /// running under.
+        fn build_resource(
+            service_name: &str,
+            runtime_metadata: &RuntimeMetadata,
+            target: &TargetDetails,
+        ) -> Resource {
+            let mut attributes = vec![
+                KeyValue::new("service.name", service_name.to_string()),
+                KeyValue::new("process.pid", target.pid as i64),
+                KeyValue::new(
+                    "process.executable.path",
+                    target.exe_path.to_string_lossy().into_owned(),
+                ),
+                KeyValue::new("process.runtime.name", "rustc"),
+                KeyValue::new("telemetry.sdk.name", "opentelemetry"),
+                KeyValue::new("telemetry.sdk.language", "rust"),
+                KeyValue::new(
+                    "telemetry.sdk.version",
+                    runtime_metadata.agent_version,
+                ),
+                KeyValue::new("agent.name", runtime_metadata.agent_name),
+                KeyValue::new(
+                    "agent.ebpf_loader.version",
+                    runtime_metadata.ebpf_loader_version,
+                ),
+                KeyValue::new(
+                    "host.kernel.version",
+                    runtime_metadata.host_kernel_version.clone(),
+                ),
+            ];
+
+            if let Some(ref host_name) = runtime_metadata.host_name {
+                attributes.push(KeyValue::new("host.name", host_name.clone()));
+            }
+
+            Resource::new(attributes)
+        }
+
         pub fn tracer(&self) -> &Tracer {
             &self.tracer
         }
@@ -214,53 +564,119 @@ mod opentelemetry_controller {
 
 mod process {
     use super::errors::{Error, Result};
+    use dashmap::mapref::entry::Entry;
+    use dashmap::DashMap;
     use goblin::elf::Elf;
-    use log::{debug, info};
+    use log::{debug, info, warn};
     use memmap2::Mmap;
+    use nix::sys::ptrace;
+    use nix::sys::signal::{kill, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::libc::_exit;
+    use nix::unistd::{execvp, fork, ForkResult, Pid};
     use procfs::process::Process;
     use rustc_demangle::demangle;
     use std::collections::HashMap;
+    use std::ffi::CString;
     use std::fs::File;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
     use std::time::Duration;
+    use tokio::sync::watch;
 
     pub struct TargetArgs {
         pub exe_path: Option<String>,
         pub pid: Option<i32>,
+        pub command: Option<Vec<String>>,
     }
 
     impl TargetArgs {
         pub fn validate(&self) -> Result<()> {
-            if self.exe_path.is_none() && self.pid.is_none() {
+            let provided = [self.exe_path.is_some(), self.pid.is_some(), self.command.is_some()]
+                .iter()
+                .filter(|p| **p)
+                .count();
+
+            if provided == 0 {
                 return Err(Error::InvalidTarget(
-                    "Either OTEL_TARGET_EXE or OTEL_TARGET_PID must be set".to_string(),
+                    "One of OTEL_TARGET_EXE, OTEL_TARGET_PID or OTEL_TARGET_CMD must be set"
+                        .to_string(),
                 ));
             }
+
+            if provided > 1 {
+                return Err(Error::InvalidTarget(
+                    "OTEL_TARGET_EXE, OTEL_TARGET_PID and OTEL_TARGET_CMD are mutually exclusive"
+                        .to_string(),
+                ));
+            }
+
             Ok(())
         }
     }
 
+    /// The binary a `FunctionInfo` was resolved from, so per-library
+    /// instrumentors know which mapping to attach a uprobe to.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum ModuleKind {
+        MainExecutable,
+        Library(String),
+    }
+
     #[derive(Debug, Clone)]
     pub struct FunctionInfo {
         pub name: String,
         pub demangled_name: String,
         pub address: u64,
         pub size: u64,
+        /// Offset of `address` inside the backing ELF file, i.e. where a
+        /// uprobe must be attached instead of the virtual address.
+        pub file_offset: u64,
+        pub module: ModuleKind,
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub struct TargetDetails {
         pub pid: i32,
         pub exe_path: PathBuf,
-        pub functions: Vec<FunctionInfo>,
-        pub libraries: Vec<String>,
+        pub functions: Arc<[FunctionInfo]>,
+        pub libraries: Arc<[String]>,
+        /// Runtime load base of each backing file that is both listed in
+        /// `libraries` (or is the main executable) and currently mapped into
+        /// the process, as seen in `/proc/<pid>/maps`. Lets an instrumentor
+        /// translate between a live virtual address and the on-disk offset
+        /// recorded on `FunctionInfo`.
+        pub module_load_bases: HashMap<PathBuf, u64>,
     }
 
-    pub struct Analyzer;
+    /// Parsed result of a single binary on disk, shared across every PID
+    /// that happens to run the same executable (e.g. a prefork server).
+    #[derive(Debug)]
+    struct BinaryAnalysis {
+        functions: Arc<[FunctionInfo]>,
+        libraries: Arc<[String]>,
+    }
+
+    /// A binary analysis that either completed (`Ready`) or is being parsed
+    /// by some other caller (`Pending`). The `watch` channel lets any number
+    /// of waiters subscribe to the same in-flight result without depending
+    /// on the future that is awaiting it.
+    enum CacheEntry {
+        Ready(Arc<BinaryAnalysis>),
+        Pending(watch::Receiver<Option<Arc<BinaryAnalysis>>>),
+    }
+
+    pub struct Analyzer {
+        /// Keyed by canonicalized exe path so multiple PIDs running the same
+        /// binary only pay for one `goblin` parse.
+        binary_cache: Arc<DashMap<PathBuf, CacheEntry>>,
+    }
 
     impl Analyzer {
         pub fn new() -> Self {
-            Self
+            Self {
+                binary_cache: Arc::new(DashMap::new()),
+            }
         }
 
         pub async fn discover_process(&self, target: &TargetArgs) -> Result<i32> {
@@ -271,6 +687,11 @@ mod process {
                 return Ok(proc.pid());
             }
 
+            if let Some(ref command) = target.command {
+                let pid = Self::launch_and_trace(command)?;
+                return Ok(pid.as_raw());
+            }
+
             if let Some(ref exe_path) = target.exe_path {
                 info!("Searching for process with executable: {}", exe_path);
                 loop {
@@ -294,6 +715,18 @@ mod process {
             Err(Error::InvalidTarget("No valid target specified".to_string()))
         }
 
+        /// Parses the target's main executable and, where `/proc/<pid>/maps`
+        /// already backs them with a mapped file, its dependent libraries.
+        ///
+        /// For a `--target-cmd` launched target this runs while the child is
+        /// still stopped at the post-`execve` trap, before the dynamic linker
+        /// has mapped anything beyond the main executable and its
+        /// interpreter: `elf.libraries`' sonames won't resolve to a path yet,
+        /// so no per-library functions are found on this pass. A target
+        /// relaunched by `--on-target-exit=restart` is stopped at the same
+        /// point and has the same limitation; attaching to an already-running
+        /// process via `--target-pid` or `--target-exe` analyzes one whose
+        /// libraries are already mapped.
         pub async fn analyze(
             &self,
             pid: i32,
@@ -306,9 +739,164 @@ mod process {
                 .exe()
                 .map_err(|e| Error::BinaryAnalysis(format!("Failed to get exe path: {}", e)))?;
 
-            info!("Analyzing binary: {:?}", exe_path);
+            let main_analysis = self
+                .analyze_binary(&exe_path, relevant_funcs.clone(), ModuleKind::MainExecutable)
+                .await?;
+
+            info!(
+                "Found {} relevant functions in main executable",
+                main_analysis.functions.len()
+            );
+
+            let module_load_bases =
+                Self::resolve_load_bases(&proc, &exe_path, &main_analysis.libraries);
+
+            if !main_analysis.libraries.is_empty()
+                && module_load_bases.keys().all(|path| path == &exe_path)
+            {
+                warn!(
+                    "PID {} declares {} dependent librar{} but none are mapped in \
+                     /proc/{}/maps yet (the dynamic linker may not have run); \
+                     per-library instrumentation will be skipped until a later analysis \
+                     pass sees them mapped",
+                    pid,
+                    main_analysis.libraries.len(),
+                    if main_analysis.libraries.len() == 1 { "y" } else { "ies" },
+                    pid
+                );
+            }
+
+            // `libraries` only records sonames (e.g. "libssl.so.1.1"); resolve
+            // and parse each one that `/proc/<pid>/maps` actually backs with a
+            // file, so instrumentors can attach uprobes inside shared
+            // libraries too, not just the main executable.
+            let mut functions: Vec<FunctionInfo> = main_analysis.functions.iter().cloned().collect();
+
+            for lib_path in module_load_bases.keys() {
+                if lib_path == &exe_path {
+                    continue;
+                }
+
+                let module_name = lib_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| lib_path.to_string_lossy().into_owned());
+
+                match self
+                    .analyze_binary(
+                        lib_path,
+                        relevant_funcs.clone(),
+                        ModuleKind::Library(module_name.clone()),
+                    )
+                    .await
+                {
+                    Ok(lib_analysis) => {
+                        info!(
+                            "Found {} relevant functions in library {}",
+                            lib_analysis.functions.len(),
+                            module_name
+                        );
+                        functions.extend(lib_analysis.functions.iter().cloned());
+                    }
+                    Err(e) => {
+                        warn!("Failed to analyze library {:?}: {}", lib_path, e);
+                    }
+                }
+            }
+
+            Ok(TargetDetails {
+                pid,
+                exe_path,
+                functions: Arc::from(functions),
+                libraries: Arc::clone(&main_analysis.libraries),
+                module_load_bases,
+            })
+        }
+
+        /// Returns the parsed `functions`/`libraries` for `exe_path`, parsing
+        /// it at most once no matter how many PIDs (or modules) share the
+        /// binary. `module_kind` tags every `FunctionInfo` parsed on a cache
+        /// miss; a path is expected to be consistently either the main
+        /// executable or a library, so the tag is fixed by whichever call
+        /// populates the cache first.
+        ///
+        /// Concurrent callers for the same path subscribe to the same
+        /// in-flight parse instead of starting their own. The parse itself
+        /// runs in a detached `spawn_blocking` task — cancel-safe (dropping
+        /// the caller that kicked it off doesn't abort it for everyone else
+        /// still waiting) and off the async worker threads, since the parse
+        /// is blocking CPU/IO work.
+        async fn analyze_binary(
+            &self,
+            exe_path: &Path,
+            relevant_funcs: HashMap<String, ()>,
+            module_kind: ModuleKind,
+        ) -> Result<Arc<BinaryAnalysis>> {
+            let canonical = std::fs::canonicalize(exe_path).unwrap_or_else(|_| exe_path.to_path_buf());
+
+            let rx = match self.binary_cache.entry(canonical.clone()) {
+                Entry::Occupied(occ) => match occ.get() {
+                    CacheEntry::Ready(analysis) => return Ok(Arc::clone(analysis)),
+                    CacheEntry::Pending(rx) => rx.clone(),
+                },
+                Entry::Vacant(vacant) => {
+                    info!("Analyzing binary: {:?}", canonical);
+
+                    let (tx, rx) = watch::channel(None);
+                    vacant.insert(CacheEntry::Pending(rx.clone()));
+
+                    let cache = Arc::clone(&self.binary_cache);
+                    let exe_path = exe_path.to_path_buf();
+                    let cache_key = canonical.clone();
+                    tokio::task::spawn_blocking(move || {
+                        match Self::parse_binary(&exe_path, &relevant_funcs, &module_kind) {
+                            Ok(analysis) => {
+                                let analysis = Arc::new(analysis);
+                                cache.insert(cache_key, CacheEntry::Ready(Arc::clone(&analysis)));
+                                let _ = tx.send(Some(analysis));
+                            }
+                            Err(e) => {
+                                warn!("Failed to analyze binary {:?}: {}", cache_key, e);
+                                cache.remove(&cache_key);
+                                // Dropping `tx` without ever sending closes the
+                                // channel, which is how waiters learn the parse failed.
+                            }
+                        }
+                    });
+
+                    rx
+                }
+            };
+
+            Self::await_binary_analysis(rx).await
+        }
+
+        /// Awaits a `watch` channel populated by `analyze_binary`'s owning
+        /// task. Returns an error if the channel closes (the owning task
+        /// failed) before ever sending a result.
+        async fn await_binary_analysis(
+            mut rx: watch::Receiver<Option<Arc<BinaryAnalysis>>>,
+        ) -> Result<Arc<BinaryAnalysis>> {
+            loop {
+                if let Some(analysis) = rx.borrow().clone() {
+                    return Ok(analysis);
+                }
+                if rx.changed().await.is_err() {
+                    return Err(Error::BinaryAnalysis(
+                        "binary analysis failed in a concurrent caller".to_string(),
+                    ));
+                }
+            }
+        }
 
-            let file = File::open(&exe_path)
+        /// Does the actual `goblin` parse of a binary on disk. Synchronous
+        /// and CPU/IO-bound; callers run it inside `spawn_blocking`.
+        fn parse_binary(
+            exe_path: &Path,
+            relevant_funcs: &HashMap<String, ()>,
+            module_kind: &ModuleKind,
+        ) -> Result<BinaryAnalysis> {
+            let file = File::open(exe_path)
                 .map_err(|e| Error::BinaryAnalysis(format!("Failed to open binary: {}", e)))?;
 
             let mmap = unsafe {
@@ -335,14 +923,28 @@ mod process {
                             || relevant_funcs.contains_key(name)
                             || relevant_funcs.contains_key(&demangled);
 
-                        if matches {
-                            functions.push(FunctionInfo {
-                                name: name.to_string(),
-                                demangled_name: demangled,
-                                address: sym.st_value,
-                                size: sym.st_size,
-                            });
+                        if !matches {
+                            continue;
                         }
+
+                        let Some(file_offset) =
+                            Self::resolve_file_offset(&elf.program_headers, sym.st_value)
+                        else {
+                            debug!(
+                                "Skipping {} (0x{:x}): not in a loadable segment",
+                                demangled, sym.st_value
+                            );
+                            continue;
+                        };
+
+                        functions.push(FunctionInfo {
+                            name: name.to_string(),
+                            demangled_name: demangled,
+                            address: sym.st_value,
+                            size: sym.st_size,
+                            file_offset,
+                            module: module_kind.clone(),
+                        });
                     }
                 }
             }
@@ -356,27 +958,346 @@ mod process {
                             || relevant_funcs.contains_key(name)
                             || relevant_funcs.contains_key(&demangled);
 
-                        if matches {
-                            functions.push(FunctionInfo {
-                                name: name.to_string(),
-                                demangled_name: demangled,
-                                address: sym.st_value,
-                                size: sym.st_size,
-                            });
+                        if !matches {
+                            continue;
                         }
+
+                        let Some(file_offset) =
+                            Self::resolve_file_offset(&elf.program_headers, sym.st_value)
+                        else {
+                            debug!(
+                                "Skipping {} (0x{:x}): not in a loadable segment",
+                                demangled, sym.st_value
+                            );
+                            continue;
+                        };
+
+                        functions.push(FunctionInfo {
+                            name: name.to_string(),
+                            demangled_name: demangled,
+                            address: sym.st_value,
+                            size: sym.st_size,
+                            file_offset,
+                            module: module_kind.clone(),
+                        });
                     }
                 }
             }
 
-            info!("Found {} relevant functions", functions.len());
-
-            Ok(TargetDetails {
-                pid,
-                exe_path,
-                functions,
-                libraries,
+            Ok(BinaryAnalysis {
+                functions: Arc::from(functions),
+                libraries: Arc::from(libraries),
             })
         }
+
+        /// Translates a symbol's virtual address into a file offset by
+        /// finding the `PT_LOAD` segment that maps it, per the ELF rule
+        /// `file_offset = st_value - p_vaddr + p_offset`. Returns `None` for
+        /// symbols outside any loadable segment (e.g. in debug-only sections).
+        fn resolve_file_offset(
+            program_headers: &[goblin::elf::program_header::ProgramHeader],
+            st_value: u64,
+        ) -> Option<u64> {
+            program_headers
+                .iter()
+                .find(|ph| {
+                    ph.p_type == goblin::elf::program_header::PT_LOAD
+                        && st_value >= ph.p_vaddr
+                        && st_value < ph.p_vaddr + ph.p_memsz
+                })
+                .map(|ph| st_value - ph.p_vaddr + ph.p_offset)
+        }
+
+        /// Reads `/proc/<pid>/maps` to find the runtime load base of the
+        /// main executable and any of `libraries` currently mapped into the
+        /// process. Assumes `p_vaddr - p_offset` is constant across a
+        /// module's `PT_LOAD` segments, which holds for normal toolchain
+        /// output, so `load_base = map.address - map.offset`.
+        fn resolve_load_bases(
+            proc: &Process,
+            exe_path: &PathBuf,
+            libraries: &[String],
+        ) -> HashMap<PathBuf, u64> {
+            let mut bases = HashMap::new();
+
+            let maps = match proc.maps() {
+                Ok(maps) => maps,
+                Err(e) => {
+                    warn!("Failed to read /proc/{}/maps: {}", proc.pid(), e);
+                    return bases;
+                }
+            };
+
+            for map in maps {
+                let procfs::process::MMapPath::Path(path) = &map.pathname else {
+                    continue;
+                };
+
+                let is_relevant = path == exe_path
+                    || path
+                        .file_name()
+                        .map(|name| libraries.iter().any(|lib| name.to_string_lossy() == *lib))
+                        .unwrap_or(false);
+
+                if is_relevant {
+                    let load_base = Self::load_base_from_map(map.address.0, map.offset);
+                    bases.entry(path.clone()).or_insert(load_base);
+                }
+            }
+
+            bases
+        }
+
+        /// `load_base = map.address - map.offset`, saturating so a malformed
+        /// or adversarial `/proc/<pid>/maps` entry can't underflow.
+        fn load_base_from_map(address: u64, offset: u64) -> u64 {
+            address.saturating_sub(offset)
+        }
+
+        /// Forks and execs `command`, stopping the child right after `exec`
+        /// via `ptrace(TRACEME)` so the caller can analyze/instrument it
+        /// before it runs any of its own code. The child is left stopped;
+        /// call `resume_launched` once instrumentation is attached.
+        fn launch_and_trace(command: &[String]) -> Result<Pid> {
+            let program = command
+                .first()
+                .ok_or_else(|| Error::InvalidTarget("target command is empty".to_string()))?;
+            let prog_c = CString::new(program.as_str())
+                .map_err(|e| Error::InvalidTarget(format!("invalid target command: {}", e)))?;
+            let argv_c: Vec<CString> = command
+                .iter()
+                .map(|arg| {
+                    CString::new(arg.as_str())
+                        .map_err(|e| Error::InvalidTarget(format!("invalid argument: {}", e)))
+                })
+                .collect::<Result<_>>()?;
+
+            match unsafe { fork() }
+                .map_err(|e| Error::ProcessLaunch(format!("fork failed: {}", e)))?
+            {
+                ForkResult::Parent { child } => {
+                    match waitpid(child, None) {
+                        Ok(WaitStatus::Stopped(pid, _)) => {
+                            info!("Launched target {:?}, stopped at exec: PID {}", command, pid);
+                            Ok(pid)
+                        }
+                        Ok(status) => Err(Error::ProcessLaunch(format!(
+                            "unexpected status while launching target: {:?}",
+                            status
+                        ))),
+                        Err(e) => Err(Error::ProcessLaunch(format!(
+                            "waitpid on launched target failed: {}",
+                            e
+                        ))),
+                    }
+                }
+                ForkResult::Child => {
+                    // Past this point we're a single-threaded copy of a
+                    // multi-threaded tokio process: only async-signal-safe
+                    // calls are safe to make. `std::process::exit` runs
+                    // Rust/libc atexit cleanup (stdio flushing, destructors),
+                    // which can deadlock on a lock another thread held at
+                    // fork time, wedging both the child and the parent's
+                    // `waitpid` above. `_exit` skips all of that.
+                    if ptrace::traceme().is_err() {
+                        unsafe { _exit(127) };
+                    }
+                    let _ = execvp(&prog_c, &argv_c);
+                    // execvp only returns on failure.
+                    unsafe { _exit(127) };
+                }
+            }
+        }
+
+        /// Resumes a child previously stopped by `launch_and_trace`, detaching
+        /// it from ptrace in the same call. `ptrace::cont` would leave the
+        /// agent as the child's tracer for the rest of its life, turning
+        /// every later signal delivery (e.g. `--stop-signal`) into a
+        /// ptrace signal-delivery-stop instead of the signal's real
+        /// disposition -- `detach` both resumes it and hands it back to
+        /// the kernel's normal signal handling.
+        pub fn resume_launched(pid: i32) -> Result<()> {
+            ptrace::detach(Pid::from_raw(pid), None)
+                .map_err(|e| Error::ProcessLaunch(format!("failed to resume PID {}: {}", pid, e)))
+        }
+
+        /// Best-effort kill of a launched child, used when analysis/instrumentation
+        /// fails before the child has been resumed.
+        pub fn kill_launched(pid: i32) {
+            if let Err(e) = kill(Pid::from_raw(pid), Signal::SIGKILL) {
+                warn!("Failed to kill launched target PID {}: {}", pid, e);
+            }
+            let _ = waitpid(Pid::from_raw(pid), None);
+        }
+
+        /// Reaps a launched child on agent shutdown and translates its
+        /// termination into a process exit code.
+        pub fn reap_launched(pid: i32) -> Result<i32> {
+            match waitpid(Pid::from_raw(pid), None) {
+                Ok(WaitStatus::Exited(_, code)) => Ok(code),
+                Ok(WaitStatus::Signaled(_, signal, _)) => Ok(128 + signal as i32),
+                Ok(status) => {
+                    warn!("Unexpected wait status for launched target: {:?}", status);
+                    Ok(0)
+                }
+                Err(e) => Err(Error::ProcessLaunch(format!(
+                    "failed to reap launched target PID {}: {}",
+                    pid, e
+                ))),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::Analyzer;
+        use goblin::elf::program_header::{ProgramHeader, PT_LOAD};
+
+        fn load_segment(p_vaddr: u64, p_memsz: u64, p_offset: u64) -> ProgramHeader {
+            ProgramHeader {
+                p_type: PT_LOAD,
+                p_vaddr,
+                p_memsz,
+                p_offset,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn resolve_file_offset_translates_address_within_segment() {
+            let headers = vec![load_segment(0x1000, 0x2000, 0x400)];
+            assert_eq!(
+                Analyzer::resolve_file_offset(&headers, 0x1050),
+                Some(0x450)
+            );
+        }
+
+        #[test]
+        fn resolve_file_offset_returns_none_outside_any_segment() {
+            let headers = vec![load_segment(0x1000, 0x2000, 0x400)];
+            assert_eq!(Analyzer::resolve_file_offset(&headers, 0x5000), None);
+        }
+
+        #[test]
+        fn resolve_file_offset_picks_the_containing_segment_among_several() {
+            let headers = vec![
+                load_segment(0x1000, 0x1000, 0x0),
+                load_segment(0x3000, 0x1000, 0x2000),
+            ];
+            assert_eq!(
+                Analyzer::resolve_file_offset(&headers, 0x3100),
+                Some(0x2100)
+            );
+        }
+
+        #[test]
+        fn load_base_from_map_subtracts_offset_from_address() {
+            assert_eq!(Analyzer::load_base_from_map(0x5000, 0x1000), 0x4000);
+        }
+
+        #[test]
+        fn load_base_from_map_saturates_instead_of_underflowing() {
+            assert_eq!(Analyzer::load_base_from_map(0x100, 0x1000), 0);
+        }
+    }
+}
+
+mod self_telemetry {
+    use log::info;
+    use opentelemetry::trace::{Span, SpanKind, Tracer};
+    use opentelemetry::KeyValue;
+    use opentelemetry_sdk::trace::Tracer as SdkTracer;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Static facts about this agent build and the host it's running on,
+    /// attached to every exported span's `Resource` so a collector can tell
+    /// builds and hosts apart without operator-supplied attributes.
+    pub struct RuntimeMetadata {
+        pub agent_name: &'static str,
+        pub agent_version: &'static str,
+        pub host_name: Option<String>,
+        pub host_kernel_version: String,
+        pub ebpf_loader_version: &'static str,
+    }
+
+    impl RuntimeMetadata {
+        pub fn detect() -> Self {
+            let uname = nix::sys::utsname::uname().ok();
+
+            Self {
+                agent_name: env!("CARGO_PKG_NAME"),
+                agent_version: env!("CARGO_PKG_VERSION"),
+                host_name: uname
+                    .as_ref()
+                    .map(|u| u.nodename().to_string_lossy().into_owned()),
+                host_kernel_version: uname
+                    .as_ref()
+                    .map(|u| u.release().to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                // No standalone eBPF loader yet; the agent binary is its own loader.
+                ebpf_loader_version: env!("CARGO_PKG_VERSION"),
+            }
+        }
+    }
+
+    /// Operational counters tracking whether the agent is keeping up and
+    /// whether instrumentors actually attached, so operators don't have to
+    /// infer it from scattered `warn!` lines.
+    #[derive(Default)]
+    pub struct AgentMetrics {
+        pub events_received: AtomicU64,
+        pub events_dropped: AtomicU64,
+        pub spans_started: AtomicU64,
+        pub spans_ended: AtomicU64,
+        pub instrumentors_loaded: AtomicU64,
+        pub instrumentors_skipped: AtomicU64,
+    }
+
+    impl AgentMetrics {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Flushes the current counters as a span through `tracer` (the
+        /// same pipeline used for application spans — with `--stdout` that
+        /// prints directly to the terminal) and as an info-level log line.
+        pub fn report(&self, tracer: &SdkTracer) {
+            let events_received = self.events_received.load(Ordering::Relaxed);
+            let events_dropped = self.events_dropped.load(Ordering::Relaxed);
+            let spans_started = self.spans_started.load(Ordering::Relaxed);
+            let spans_ended = self.spans_ended.load(Ordering::Relaxed);
+            let instrumentors_loaded = self.instrumentors_loaded.load(Ordering::Relaxed);
+            let instrumentors_skipped = self.instrumentors_skipped.load(Ordering::Relaxed);
+
+            let mut span = tracer
+                .span_builder("agent.self_telemetry")
+                .with_kind(SpanKind::Internal)
+                .start(tracer);
+
+            span.set_attribute(KeyValue::new("agent.events_received", events_received as i64));
+            span.set_attribute(KeyValue::new("agent.events_dropped", events_dropped as i64));
+            span.set_attribute(KeyValue::new("agent.spans_started", spans_started as i64));
+            span.set_attribute(KeyValue::new("agent.spans_ended", spans_ended as i64));
+            span.set_attribute(KeyValue::new(
+                "agent.instrumentors_loaded",
+                instrumentors_loaded as i64,
+            ));
+            span.set_attribute(KeyValue::new(
+                "agent.instrumentors_skipped",
+                instrumentors_skipped as i64,
+            ));
+            span.end();
+
+            info!(
+                "agent self-telemetry: events_received={} events_dropped={} spans_started={} spans_ended={} instrumentors_loaded={} instrumentors_skipped={}",
+                events_received,
+                events_dropped,
+                spans_started,
+                spans_ended,
+                instrumentors_loaded,
+                instrumentors_skipped,
+            );
+        }
     }
 }
 
@@ -384,12 +1305,26 @@ mod instrumentors {
     use super::errors::{Error, Result};
     use super::opentelemetry_controller::Controller;
     use super::process::TargetDetails;
+    use super::self_telemetry::AgentMetrics;
+    use super::supervisor;
     use async_trait::async_trait;
     use log::{info, warn};
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use std::sync::Arc;
+    use std::time::Duration;
     use tokio::sync::broadcast;
 
+    /// How `Manager::run` stopped, distinguishing an agent-initiated shutdown
+    /// (signaled via `shutdown_rx`, which instead returns `Error::Interrupted`)
+    /// from the two other ways the run loop can end on its own.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum RunOutcome {
+        /// The target process was no longer alive.
+        TargetExited,
+        /// Every instrumentor finished and the events channel drained.
+        EventsHandlerDone,
+    }
+
     #[derive(Debug, Clone)]
     pub struct Event {
         pub library: String,
@@ -398,41 +1333,74 @@ mod instrumentors {
         pub end_time: u64,
         pub trace_id: [u8; 16],
         pub span_id: [u8; 8],
+        /// Parent span ID decoded from an incoming `traceparent` header (or
+        /// a caller's uprobe) by the instrumentor that captured this event.
+        /// `None` means this event starts a new local root.
+        pub parent_span_id: Option<[u8; 8]>,
         pub attributes: Vec<(String, String)>,
     }
 
+    /// Handed to instrumentors in place of a raw `mpsc::Sender<Event>` so
+    /// sends that can't fit the 1024-slot channel are counted instead of
+    /// silently vanishing or backpressuring the instrumentor.
+    #[derive(Clone)]
+    pub struct EventSender {
+        tx: tokio::sync::mpsc::Sender<Event>,
+        metrics: Arc<AgentMetrics>,
+    }
+
+    impl EventSender {
+        fn new(tx: tokio::sync::mpsc::Sender<Event>, metrics: Arc<AgentMetrics>) -> Self {
+            Self { tx, metrics }
+        }
+
+        /// Enqueues `event` without blocking, dropping (and counting) it if
+        /// the channel is full.
+        pub fn try_send(&self, event: Event) {
+            if self.tx.try_send(event).is_err() {
+                self.metrics
+                    .events_dropped
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            }
+        }
+    }
+
     #[async_trait]
     pub trait Instrumentor: Send + Sync {
         fn library_name(&self) -> &str;
         fn func_names(&self) -> Vec<&str>;
         async fn load(&mut self, target: &TargetDetails) -> Result<()>;
-        async fn run(&self, events_tx: tokio::sync::mpsc::Sender<Event>) -> Result<()>;
+        async fn run(&self, events_tx: EventSender) -> Result<()>;
         fn close(&mut self);
     }
 
     pub struct Manager {
-        instrumentors: HashMap<String, Box<dyn Instrumentor>>,
-        controller: Arc<Controller>,
+        instrumentors: HashMap<String, Arc<tokio::sync::Mutex<Box<dyn Instrumentor>>>>,
+        metrics: Arc<AgentMetrics>,
     }
 
     impl Manager {
-        pub fn new(controller: Arc<Controller>) -> Self {
-            let mut instrumentors: HashMap<String, Box<dyn Instrumentor>> = HashMap::new();
+        pub fn new(metrics: Arc<AgentMetrics>) -> Self {
+            let mut instrumentors: HashMap<String, Arc<tokio::sync::Mutex<Box<dyn Instrumentor>>>> =
+                HashMap::new();
 
             instrumentors.insert(
                 "hyper".to_string(),
-                Box::new(super::hyper_instrumentor::HyperInstrumentor::new()),
+                Arc::new(tokio::sync::Mutex::new(Box::new(
+                    super::hyper_instrumentor::HyperInstrumentor::new(),
+                ))),
             );
 
             Self {
                 instrumentors,
-                controller,
+                metrics,
             }
         }
 
-        pub fn get_relevant_funcs(&self) -> HashMap<String, ()> {
+        pub async fn get_relevant_funcs(&self) -> HashMap<String, ()> {
             let mut funcs = HashMap::new();
             for inst in self.instrumentors.values() {
+                let inst = inst.lock().await;
                 for func in inst.func_names() {
                     funcs.insert(func.to_string(), ());
                 }
@@ -440,7 +1408,7 @@ mod instrumentors {
             funcs
         }
 
-        pub fn filter_unused_instrumentors(&self, target: &TargetDetails) {
+        pub async fn filter_unused_instrumentors(&self, target: &TargetDetails) {
             let existing_funcs: HashMap<String, ()> = target
                 .functions
                 .iter()
@@ -448,6 +1416,7 @@ mod instrumentors {
                 .collect();
 
             for (name, inst) in &self.instrumentors {
+                let inst = inst.lock().await;
                 let found = inst
                     .func_names()
                     .iter()
@@ -467,52 +1436,165 @@ mod instrumentors {
             }
         }
 
+        /// Loads every instrumentor against `target`, returning the names of
+        /// the ones that loaded successfully. Must complete (and a launched
+        /// target must stay stopped) before `run` is called, so instrumentors
+        /// are attached before the target executes any of its own code.
+        ///
+        /// `instrumentors_loaded`/`instrumentors_skipped` are counted here,
+        /// against the actual `Instrumentor::load` outcome, rather than the
+        /// earlier func-name-matching heuristic in
+        /// `filter_unused_instrumentors`.
+        pub async fn load_all(&self, target: &TargetDetails) -> HashSet<String> {
+            let mut loaded = HashSet::new();
+            for (name, inst) in &self.instrumentors {
+                let mut inst = inst.lock().await;
+                match inst.load(target).await {
+                    Ok(()) => {
+                        loaded.insert(name.clone());
+                        self.metrics
+                            .instrumentors_loaded
+                            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
+                    Err(e) => {
+                        warn!("Instrumentor {} failed to load: {}", name, e);
+                        self.metrics
+                            .instrumentors_skipped
+                            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
+                }
+            }
+            loaded
+        }
+
         pub async fn run(
             &self,
+            loaded: &HashSet<String>,
             target: &TargetDetails,
             mut shutdown_rx: broadcast::Receiver<()>,
-        ) -> Result<()> {
+            controller: Arc<Controller>,
+        ) -> Result<RunOutcome> {
             let (events_tx, mut events_rx) = tokio::sync::mpsc::channel::<Event>(1024);
 
             info!("Starting instrumentors for {} libraries", self.instrumentors.len());
 
-            let controller = Arc::clone(&self.controller);
+            let mut instrumentor_tasks = Vec::with_capacity(self.instrumentors.len());
+            for (name, inst) in &self.instrumentors {
+                if !loaded.contains(name) {
+                    continue;
+                }
+
+                let name = name.clone();
+                let inst = Arc::clone(inst);
+                let events_tx = EventSender::new(events_tx.clone(), Arc::clone(&self.metrics));
+
+                instrumentor_tasks.push(tokio::spawn(async move {
+                    let inst = inst.lock().await;
+                    if let Err(e) = inst.run(events_tx).await {
+                        warn!("Instrumentor {} exited with error: {}", name, e);
+                    }
+                }));
+            }
+
+            // Drop our own sender so the channel closes once every
+            // instrumentor task (each holding a clone) has finished;
+            // otherwise `events_rx.recv()` never observes `None` and the
+            // `events_handler` branch below can never complete.
+            drop(events_tx);
+
+            let metrics = Arc::clone(&self.metrics);
             let events_handler = tokio::spawn(async move {
-                use opentelemetry::trace::{Span, SpanKind, Tracer};
+                use opentelemetry::trace::{
+                    Span, SpanContext, SpanId, SpanKind, TraceContextExt, TraceFlags, TraceId,
+                    TraceState, Tracer,
+                };
+                use opentelemetry::Context;
+                use std::sync::atomic::Ordering;
+                use std::time::{Duration, UNIX_EPOCH};
+
                 let tracer = controller.tracer();
 
                 while let Some(event) = events_rx.recv().await {
+                    metrics.events_received.fetch_add(1, Ordering::Relaxed);
+
+                    let trace_id = TraceId::from_bytes(event.trace_id);
+
+                    // A remote parent (decoded from an incoming `traceparent`
+                    // header) makes this span join that caller's trace;
+                    // without one it starts a new local trace rooted here.
+                    let parent_cx = match event.parent_span_id {
+                        Some(parent_span_id) => Context::new().with_remote_span_context(
+                            SpanContext::new(
+                                trace_id,
+                                SpanId::from_bytes(parent_span_id),
+                                TraceFlags::SAMPLED,
+                                true,
+                                TraceState::default(),
+                            ),
+                        ),
+                        None => Context::new(),
+                    };
+
                     let mut span = tracer
                         .span_builder(event.name.clone())
                         .with_kind(SpanKind::Server)
-                        .start(tracer);
+                        .with_trace_id(trace_id)
+                        .with_span_id(SpanId::from_bytes(event.span_id))
+                        .with_start_time(UNIX_EPOCH + Duration::from_nanos(event.start_time))
+                        .start_with_context(tracer, &parent_cx);
+                    metrics.spans_started.fetch_add(1, Ordering::Relaxed);
 
                     for (key, value) in event.attributes {
                         span.set_attribute(opentelemetry::KeyValue::new(key, value));
                     }
 
-                    span.end();
+                    span.end_with_timestamp(UNIX_EPOCH + Duration::from_nanos(event.end_time));
+                    metrics.spans_ended.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+
+            let target_pid = target.pid;
+            let mut liveness = tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    if !supervisor::is_alive(target_pid) {
+                        return;
+                    }
                 }
             });
 
             tokio::select! {
                 _ = shutdown_rx.recv() => {
                     info!("Shutdown signal received");
+                    liveness.abort();
+                    for task in &instrumentor_tasks {
+                        task.abort();
+                    }
                     return Err(Error::Interrupted);
                 }
+                _ = &mut liveness => {
+                    info!("Target process (PID {}) is no longer alive", target_pid);
+                    for task in &instrumentor_tasks {
+                        task.abort();
+                    }
+                    return Ok(RunOutcome::TargetExited);
+                }
                 _ = events_handler => {
                     info!("Events handler completed");
+                    for task in &instrumentor_tasks {
+                        task.abort();
+                    }
                 }
             }
 
-            Ok(())
+            Ok(RunOutcome::EventsHandlerDone)
         }
     }
 }
 
 mod hyper_instrumentor {
     use super::errors::Result;
-    use super::instrumentors::{Event, Instrumentor};
+    use super::instrumentors::{EventSender, Instrumentor};
     use super::process::TargetDetails;
     use async_trait::async_trait;
 
@@ -545,7 +1627,7 @@ mod hyper_instrumentor {
             Ok(())
         }
 
-        async fn run(&self, _events_tx: tokio::sync::mpsc::Sender<Event>) -> Result<()> {
+        async fn run(&self, _events_tx: EventSender) -> Result<()> {
             Ok(())
         }
 
@@ -555,3 +1637,109 @@ mod hyper_instrumentor {
     }
 }
 
+mod supervisor {
+    use super::errors::{Error, Result};
+    use clap::ValueEnum;
+    use log::{info, warn};
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::Pid;
+    use procfs::process::Process;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    /// What the agent does when it notices the target process has exited.
+    #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+    #[clap(rename_all = "kebab-case")]
+    pub enum OnTargetExit {
+        /// Stop the agent.
+        Shutdown,
+        /// Keep exporting buffered spans, then idle.
+        Detach,
+        /// Re-run discovery/analysis and re-load instrumentors against the new PID.
+        Restart,
+    }
+
+    /// Checks whether `pid` still refers to a live process.
+    ///
+    /// `/proc/<pid>` persists for a zombie until its parent reaps it, so a
+    /// bare `Process::new(pid).is_ok()` would report a zombie target as
+    /// alive for as long as the agent happens to delay reaping it. Treat
+    /// state `Z` as dead instead.
+    pub fn is_alive(pid: i32) -> bool {
+        match Process::new(pid).and_then(|proc| proc.stat()) {
+            Ok(stat) => stat.state != 'Z',
+            Err(_) => false,
+        }
+    }
+
+    /// Parses a signal name such as `"SIGTERM"` or `"TERM"`.
+    pub fn parse_signal(name: &str) -> Result<Signal> {
+        let upper = name.trim().to_uppercase();
+        let normalized = if upper.starts_with("SIG") {
+            upper
+        } else {
+            format!("SIG{}", upper)
+        };
+        Signal::from_str(&normalized)
+            .map_err(|_| Error::InvalidTarget(format!("unknown stop signal: {}", name)))
+    }
+
+    /// Signals `pid` and waits up to `timeout` for it to exit, escalating to
+    /// `SIGKILL` if it is still alive once the timeout elapses.
+    pub async fn stop_target(pid: i32, signal: Signal, timeout: Duration) {
+        if !is_alive(pid) {
+            return;
+        }
+
+        info!("Sending {:?} to target PID {}", signal, pid);
+        if let Err(e) = kill(Pid::from_raw(pid), signal) {
+            warn!("Failed to signal target PID {}: {}", pid, e);
+            return;
+        }
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        while tokio::time::Instant::now() < deadline {
+            if !is_alive(pid) {
+                info!("Target PID {} exited after {:?}", pid, signal);
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        if is_alive(pid) {
+            warn!(
+                "Target PID {} still alive {:?} after {:?}, escalating to SIGKILL",
+                pid, signal, timeout
+            );
+            let _ = kill(Pid::from_raw(pid), Signal::SIGKILL);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::parse_signal;
+        use nix::sys::signal::Signal;
+
+        #[test]
+        fn parse_signal_accepts_bare_name() {
+            assert_eq!(parse_signal("TERM").unwrap(), Signal::SIGTERM);
+        }
+
+        #[test]
+        fn parse_signal_accepts_sig_prefixed_name() {
+            assert_eq!(parse_signal("SIGTERM").unwrap(), Signal::SIGTERM);
+        }
+
+        #[test]
+        fn parse_signal_is_case_insensitive_and_trims_whitespace() {
+            assert_eq!(parse_signal(" sigkill ").unwrap(), Signal::SIGKILL);
+            assert_eq!(parse_signal("kill").unwrap(), Signal::SIGKILL);
+        }
+
+        #[test]
+        fn parse_signal_rejects_unknown_name() {
+            assert!(parse_signal("NOTASIGNAL").is_err());
+        }
+    }
+}
+